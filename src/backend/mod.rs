@@ -0,0 +1,137 @@
+//! Platform speech synthesis backends.
+//!
+//! `SpeechApp` talks to the operating system's text-to-speech engine through
+//! the [`SpeechBackend`] trait instead of a concrete SAPI type, so the rest
+//! of the crate can stay platform-agnostic. `build_backend` picks the right
+//! implementation for the target at compile time.
+
+#[cfg(target_os = "windows")]
+mod sapi;
+#[cfg(target_os = "windows")]
+pub use sapi::SapiBackend;
+
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+mod speech_dispatcher;
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+pub use speech_dispatcher::SpeechDispatcherBackend;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::WebSpeechBackend;
+
+use std::error::Error;
+
+/// Receives asynchronous notifications from a [`SpeechBackend`].
+///
+/// This mirrors `sapi_lite::tts::EventHandler` but is backend-agnostic, so
+/// callers don't need to depend on SAPI types to react to speech events.
+pub trait EventHandler: Send + Sync {
+    /// Called when the utterance with the given id has finished speaking.
+    fn on_speech_finished(&self, id: u32);
+
+    /// Called as speech progresses, reporting the word or sentence
+    /// currently being spoken as a `(offset, length)` pair of UTF-16 code
+    /// units into the utterance text. Backends that can't report word
+    /// boundaries simply never call this.
+    fn on_word_boundary(&self, _utf16_offset: u32, _utf16_length: u32) {}
+}
+
+/// A single installed voice, as reported by a backend.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VoiceInfo {
+    pub name: String,
+}
+
+/// A platform text-to-speech engine.
+///
+/// Implementations wrap whatever native API the platform provides (SAPI on
+/// Windows, speech-dispatcher on Linux, the Web Speech API on wasm, ...) and
+/// expose the same small control surface so `SpeechApp` can stay generic
+/// over the backend.
+pub trait SpeechBackend: Send {
+    /// Lists the voices installed on the system, in a stable order.
+    fn list_voices(&self) -> Vec<VoiceInfo>;
+
+    /// Selects the voice with the given name, or the first available voice
+    /// if `name` is `None` or doesn't match any installed voice.
+    fn set_voice(&mut self, name: Option<&str>) -> Result<(), Box<dyn Error>>;
+
+    /// Sets the speaking rate. Follows SAPI's convention of a signed value
+    /// typically clamped to `-10..=10`, where `0` is the default rate.
+    fn set_rate(&mut self, rate: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Sets the output volume as a percentage (`0..=100`).
+    fn set_volume(&mut self, volume: u32) -> Result<(), Box<dyn Error>>;
+
+    /// Reports whether `text` is markup this backend's engine understands
+    /// and should be passed to [`speak`](SpeechBackend::speak) unmodified
+    /// rather than escaped as literal text. Only SAPI's inline SSML/XML is
+    /// supported today, so the default is `false`.
+    fn accepts_markup(&self, text: &str) -> bool {
+        let _ = text;
+        false
+    }
+
+    /// Prepares literal (non-markup) `text` for [`speak`](SpeechBackend::speak),
+    /// applying `pitch` if this backend has a way to express it. The default
+    /// ignores `pitch` and returns `text` unchanged, since most backends have
+    /// no inline pitch control.
+    fn format_speech(&self, text: &str, pitch: i32) -> String {
+        let _ = pitch;
+        String::from(text)
+    }
+
+    /// Speaks `text`, returning an utterance id that will later be passed to
+    /// [`EventHandler::on_speech_finished`]. Any speech already in progress
+    /// is purged first.
+    fn speak(&mut self, text: &str) -> Result<u32, Box<dyn Error>>;
+
+    /// Pauses speech currently in progress, leaving it resumable.
+    fn pause(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Resumes speech paused by [`pause`](SpeechBackend::pause).
+    fn resume(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Stops and discards any speech currently in progress.
+    fn stop(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Reports whether the backend is currently speaking (including while
+    /// paused).
+    fn is_speaking(&self) -> bool;
+
+    /// Synthesizes `text` to an audio file at `path` instead of the
+    /// speakers. Backends that have no native file-output API can leave
+    /// this unsupported.
+    fn synthesize_to_file(&mut self, text: &str, path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+        let _ = (text, path);
+        Err("this backend does not support exporting speech to a file".into())
+    }
+}
+
+/// Builds the speech backend appropriate for the current target platform.
+pub fn build_backend(
+    handler: impl EventHandler + 'static,
+) -> Result<Box<dyn SpeechBackend>, Box<dyn Error>> {
+    #[cfg(target_os = "windows")]
+    {
+        Ok(Box::new(SapiBackend::new(handler)?))
+    }
+    #[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+    {
+        Ok(Box::new(SpeechDispatcherBackend::new(handler)?))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Ok(Box::new(WebSpeechBackend::new(handler)?))
+    }
+    #[cfg(not(any(
+        target_os = "windows",
+        all(target_os = "linux", not(target_arch = "wasm32")),
+        target_arch = "wasm32"
+    )))]
+    {
+        let _ = handler;
+        Err("no speech backend is available for this platform".into())
+    }
+}
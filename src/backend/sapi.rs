@@ -0,0 +1,147 @@
+//! Windows backend, built on top of `sapi_lite`.
+
+use super::{EventHandler, SpeechBackend, VoiceInfo};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Checks whether `text` is well-formed SSML/SAPI XML markup that should be
+/// passed through to the synthesizer as-is rather than escaped. SAPI
+/// tolerates several top-level elements in an utterance, so `text` is
+/// wrapped in a synthetic root purely for the purpose of validation.
+fn is_valid_markup(text: &str) -> bool {
+    let trimmed = text.trim();
+    if !trimmed.starts_with('<') || !trimmed.ends_with('>') {
+        return false;
+    }
+
+    let wrapped = format!("<root>{trimmed}</root>");
+    roxmltree::Document::parse(&wrapped).is_ok()
+}
+
+/// Prepares plain text for speech: XML special characters are always
+/// escaped so the text can't be misread as markup, then optionally wrapped
+/// in SAPI's `<pitch>` markup.
+fn literal_speech_markup(text: &str, pitch: i32) -> String {
+    let escaped = crate::escape_xml_text(text);
+    if pitch == 0 {
+        escaped
+    } else {
+        format!("<pitch absmiddle=\"{pitch}\">{escaped}</pitch>")
+    }
+}
+
+fn get_voice_name(voice: &sapi_lite::tts::Voice) -> String {
+    let name = voice
+        .name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let lang = voice
+        .language()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    format!("{name} [{lang}]")
+}
+
+/// Adapts our backend-agnostic [`EventHandler`] to `sapi_lite`'s own event
+/// handler trait.
+struct SapiEventHandler(Arc<dyn EventHandler>);
+
+impl sapi_lite::tts::EventHandler for SapiEventHandler {
+    fn on_speech_finished(&self, id: u32) {
+        self.0.on_speech_finished(id);
+    }
+
+    fn on_word_boundary(&self, char_pos: u32, char_len: u32) {
+        self.0.on_word_boundary(char_pos, char_len);
+    }
+}
+
+pub struct SapiBackend {
+    synth: sapi_lite::tts::EventfulSynthesizer,
+    voices: Vec<sapi_lite::tts::Voice>,
+}
+
+impl SapiBackend {
+    pub fn new(handler: impl EventHandler + 'static) -> Result<Self, Box<dyn Error>> {
+        sapi_lite::initialize()?;
+
+        let handler: Arc<dyn EventHandler> = Arc::new(handler);
+        Ok(Self {
+            synth: sapi_lite::tts::EventfulSynthesizer::new(SapiEventHandler(handler))?,
+            voices: sapi_lite::tts::installed_voices(None, None)?.collect(),
+        })
+    }
+
+    fn get_voice_by_name(&self, name: &str) -> Option<&sapi_lite::tts::Voice> {
+        self.voices.iter().find(|voice| get_voice_name(voice) == name)
+    }
+}
+
+impl SpeechBackend for SapiBackend {
+    fn list_voices(&self) -> Vec<VoiceInfo> {
+        self.voices
+            .iter()
+            .map(|voice| VoiceInfo {
+                name: get_voice_name(voice),
+            })
+            .collect()
+    }
+
+    fn set_voice(&mut self, name: Option<&str>) -> Result<(), Box<dyn Error>> {
+        if let Some(name) = name.and_then(|name| self.get_voice_by_name(name)) {
+            self.synth.set_voice(name)?;
+        } else if !self.voices.is_empty() {
+            self.synth.set_voice(&self.voices[0])?;
+        }
+        Ok(())
+    }
+
+    fn set_rate(&mut self, rate: i32) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.set_rate(rate)?)
+    }
+
+    fn set_volume(&mut self, volume: u32) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.set_volume(volume)?)
+    }
+
+    fn accepts_markup(&self, text: &str) -> bool {
+        is_valid_markup(text)
+    }
+
+    fn format_speech(&self, text: &str, pitch: i32) -> String {
+        literal_speech_markup(text, pitch)
+    }
+
+    fn speak(&mut self, text: &str) -> Result<u32, Box<dyn Error>> {
+        self.synth.purge()?;
+        Ok(self.synth.speak(text)?)
+    }
+
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.pause()?)
+    }
+
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.resume()?)
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.purge()?)
+    }
+
+    fn is_speaking(&self) -> bool {
+        self.synth.status().map(|status| status.running).unwrap_or(false)
+    }
+
+    fn synthesize_to_file(&mut self, text: &str, path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+        Ok(self.synth.speak_to_file(path, text)?)
+    }
+}
+
+impl Drop for SapiBackend {
+    fn drop(&mut self) {
+        sapi_lite::finalize();
+    }
+}
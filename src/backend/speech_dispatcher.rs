@@ -0,0 +1,153 @@
+//! Linux backend, built on top of `speech-dispatcher`.
+
+use super::{EventHandler, SpeechBackend, VoiceInfo};
+use parking_lot::Mutex;
+use speech_dispatcher::{Connection, Priority, PunctuationMode};
+use std::error::Error;
+use std::sync::Arc;
+
+/// speech-dispatcher has no native per-word boundary event, only index
+/// marks that fire for `<mark name="..."/>` elements the caller inserts
+/// itself. So `speak` wraps `text` in SSML with a mark before each word,
+/// and this returns that SSML alongside each word's `(utf16_offset,
+/// utf16_length)` span into the original `text`, keyed by mark name (its
+/// index into the returned `Vec`) so the `on_index_mark` callback can
+/// translate a fired mark back into an `EventHandler::on_word_boundary`
+/// call.
+fn mark_up_words(text: &str) -> (String, Vec<(u32, u32)>) {
+    let mut marked = String::from("<speak>");
+    let mut spans = Vec::new();
+    let mut utf16_pos = 0u32;
+    let mut word_start: Option<(usize, u32)> = None;
+
+    for (byte_pos, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            if let Some((start, utf16_start)) = word_start.take() {
+                marked.push_str(&format!("<mark name=\"{}\"/>", spans.len()));
+                marked.push_str(&crate::escape_xml_text(&text[start..byte_pos]));
+                spans.push((utf16_start, utf16_pos - utf16_start));
+            }
+            marked.push(ch);
+        } else if word_start.is_none() {
+            word_start = Some((byte_pos, utf16_pos));
+        }
+        utf16_pos += ch.len_utf16() as u32;
+    }
+    if let Some((start, utf16_start)) = word_start {
+        marked.push_str(&format!("<mark name=\"{}\"/>", spans.len()));
+        marked.push_str(&crate::escape_xml_text(&text[start..]));
+        spans.push((utf16_start, utf16_pos - utf16_start));
+    }
+    marked.push_str("</speak>");
+
+    (marked, spans)
+}
+
+pub struct SpeechDispatcherBackend {
+    connection: Connection,
+    voices: Vec<String>,
+    next_utterance_id: u32,
+    speaking: Arc<Mutex<bool>>,
+    handler: Arc<dyn EventHandler>,
+}
+
+impl SpeechDispatcherBackend {
+    pub fn new(handler: impl EventHandler + 'static) -> Result<Self, Box<dyn Error>> {
+        let connection = Connection::open("simplettsreader", "simplettsreader", "simplettsreader", speech_dispatcher::Mode::Single)?;
+        connection.set_punctuation(PunctuationMode::None);
+
+        let voices = connection
+            .list_synthesis_voices()?
+            .into_iter()
+            .map(|voice| voice.name)
+            .collect();
+
+        Ok(Self {
+            connection,
+            voices,
+            next_utterance_id: 0,
+            speaking: Arc::new(Mutex::new(false)),
+            handler: Arc::new(handler),
+        })
+    }
+}
+
+impl SpeechBackend for SpeechDispatcherBackend {
+    fn list_voices(&self) -> Vec<VoiceInfo> {
+        self.voices
+            .iter()
+            .map(|name| VoiceInfo { name: name.clone() })
+            .collect()
+    }
+
+    fn set_voice(&mut self, name: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let name = name
+            .filter(|name| self.voices.iter().any(|voice| voice == name))
+            .or(self.voices.first().map(String::as_str));
+        if let Some(name) = name {
+            self.connection.set_synthesis_voice(name)?;
+        }
+        Ok(())
+    }
+
+    fn set_rate(&mut self, rate: i32) -> Result<(), Box<dyn Error>> {
+        // speech-dispatcher's rate runs from -100 to 100; SAPI's from -10 to
+        // 10, so scale to keep the same range our callers already clamp to.
+        Ok(self.connection.set_rate(rate.clamp(-10, 10) * 10)?)
+    }
+
+    fn set_volume(&mut self, volume: u32) -> Result<(), Box<dyn Error>> {
+        // speech-dispatcher's volume runs from -100 to 100; map our 0..=100
+        // percentage linearly onto that whole range.
+        let volume = (volume.clamp(0, 100) as i32) * 2 - 100;
+        Ok(self.connection.set_volume(volume)?)
+    }
+
+    fn speak(&mut self, text: &str) -> Result<u32, Box<dyn Error>> {
+        self.connection.stop();
+
+        self.next_utterance_id = self.next_utterance_id.wrapping_add(1);
+        let id = self.next_utterance_id;
+
+        let handler = self.handler.clone();
+        let speaking = self.speaking.clone();
+        self.connection.on_end(move |_msg_id| {
+            *speaking.lock() = false;
+            handler.on_speech_finished(id);
+        });
+
+        let (marked_up, spans) = mark_up_words(text);
+        let handler = self.handler.clone();
+        self.connection.on_index_mark(move |name| {
+            if let Some((utf16_offset, utf16_length)) =
+                name.parse::<usize>().ok().and_then(|index| spans.get(index)).copied()
+            {
+                handler.on_word_boundary(utf16_offset, utf16_length);
+            }
+        });
+
+        self.connection.say(Priority::Text, &marked_up);
+        *self.speaking.lock() = true;
+        Ok(id)
+    }
+
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> {
+        self.connection.pause();
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> {
+        self.connection.resume();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        self.connection.stop();
+        *self.speaking.lock() = false;
+        Ok(())
+    }
+
+    fn is_speaking(&self) -> bool {
+        *self.speaking.lock()
+    }
+}
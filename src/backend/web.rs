@@ -0,0 +1,125 @@
+//! wasm backend, built on the browser's `SpeechSynthesis` API via `web-sys`.
+
+use super::{EventHandler, SpeechBackend, VoiceInfo};
+use parking_lot::Mutex;
+use std::error::Error;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{SpeechSynthesisEvent, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+pub struct WebSpeechBackend {
+    synth: web_sys::SpeechSynthesis,
+    voices: Vec<SpeechSynthesisVoice>,
+    voice: Option<SpeechSynthesisVoice>,
+    rate: i32,
+    volume: u32,
+    next_utterance_id: u32,
+    speaking: Arc<Mutex<bool>>,
+    handler: Arc<dyn EventHandler>,
+}
+
+impl WebSpeechBackend {
+    pub fn new(handler: impl EventHandler + 'static) -> Result<Self, Box<dyn Error>> {
+        let window = web_sys::window().ok_or("no global `window` exists")?;
+        let synth = window.speech_synthesis().map_err(|_| "speechSynthesis is unavailable")?;
+
+        let voices = synth.get_voices().iter().map(|v| v.unchecked_into()).collect();
+
+        Ok(Self {
+            synth,
+            voices,
+            voice: None,
+            rate: 0,
+            volume: 100,
+            next_utterance_id: 0,
+            speaking: Arc::new(Mutex::new(false)),
+            handler: Arc::new(handler),
+        })
+    }
+}
+
+impl SpeechBackend for WebSpeechBackend {
+    fn list_voices(&self) -> Vec<VoiceInfo> {
+        self.voices
+            .iter()
+            .map(|voice| VoiceInfo { name: voice.name() })
+            .collect()
+    }
+
+    fn set_voice(&mut self, name: Option<&str>) -> Result<(), Box<dyn Error>> {
+        self.voice = name
+            .and_then(|name| self.voices.iter().find(|voice| voice.name() == name))
+            .or_else(|| self.voices.first())
+            .cloned();
+        Ok(())
+    }
+
+    fn set_rate(&mut self, rate: i32) -> Result<(), Box<dyn Error>> {
+        self.rate = rate.clamp(-10, 10);
+        Ok(())
+    }
+
+    fn set_volume(&mut self, volume: u32) -> Result<(), Box<dyn Error>> {
+        self.volume = volume.clamp(0, 100);
+        Ok(())
+    }
+
+    fn speak(&mut self, text: &str) -> Result<u32, Box<dyn Error>> {
+        self.synth.cancel();
+
+        let utterance = SpeechSynthesisUtterance::new_with_text(text);
+        if let Some(voice) = &self.voice {
+            utterance.set_voice(Some(voice));
+        }
+        // The Web Speech API's rate is a multiplier around 1.0 rather than
+        // SAPI's signed -10..=10 scale, so translate between them.
+        utterance.set_rate(1.0 + self.rate as f32 / 10.0);
+        utterance.set_volume(self.volume as f32 / 100.0);
+
+        self.next_utterance_id = self.next_utterance_id.wrapping_add(1);
+        let id = self.next_utterance_id;
+
+        let handler = self.handler.clone();
+        let speaking = self.speaking.clone();
+        let on_end = Closure::once(move |_event: JsValue| {
+            *speaking.lock() = false;
+            handler.on_speech_finished(id);
+        });
+        utterance.set_onend(Some(on_end.as_ref().unchecked_ref()));
+        on_end.forget();
+
+        // charIndex/charLength are counted in UTF-16 code units, same as
+        // SAPI's word-boundary event, so no conversion is needed here.
+        let handler = self.handler.clone();
+        let on_boundary = Closure::wrap(Box::new(move |event: SpeechSynthesisEvent| {
+            handler.on_word_boundary(event.char_index(), event.char_length());
+        }) as Box<dyn FnMut(SpeechSynthesisEvent)>);
+        utterance.set_onboundary(Some(on_boundary.as_ref().unchecked_ref()));
+        on_boundary.forget();
+
+        self.synth.speak(&utterance);
+        *self.speaking.lock() = true;
+        Ok(id)
+    }
+
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> {
+        self.synth.pause();
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> {
+        self.synth.resume();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        self.synth.cancel();
+        *self.speaking.lock() = false;
+        Ok(())
+    }
+
+    fn is_speaking(&self) -> bool {
+        *self.speaking.lock()
+    }
+}
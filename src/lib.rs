@@ -1,5 +1,8 @@
 slint::include_modules!();
 
+mod backend;
+
+use backend::{EventHandler, SpeechBackend};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -12,7 +15,10 @@ struct Config {
     voice_name: String,
     rate: i32,
     volume: u32,
+    pitch: i32,
     hidden: bool,
+    queue_mode: bool,
+    detect_markup: bool,
 }
 
 impl Config {
@@ -21,6 +27,7 @@ impl Config {
             if sanitize {
                 config.rate = config.rate.clamp(-10, 10);
                 config.volume = config.volume.clamp(0, 100);
+                config.pitch = config.pitch.clamp(-10, 10);
             }
             config
         } else {
@@ -39,45 +46,106 @@ impl std::default::Default for Config {
             voice_name: String::from(""),
             rate: 0,
             volume: 100,
+            pitch: 0,
             hidden: false,
+            queue_mode: false,
+            detect_markup: true,
         }
     }
 }
 
-fn get_voice_name(voice: &sapi_lite::tts::Voice) -> String {
-    let name = voice
-        .name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .into_owned();
-    let lang = voice
-        .language()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .into_owned();
-    format!("{name} [{lang}]")
+/// Escapes the characters XML/SSML markup treats as special, so plain text
+/// can be embedded inside a markup tag without being misread as markup.
+/// Shared by any backend that builds its own markup (SAPI's `<pitch>` tag,
+/// speech-dispatcher's `<mark>` tags).
+pub(crate) fn escape_xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
 }
 
-struct SapiEventHandler;
+/// Converts a `(offset, length)` pair expressed in UTF-16 code units (as
+/// reported by SAPI word-boundary events) into a byte range into `text`.
+fn utf16_span_to_byte_range(text: &str, utf16_offset: u32, utf16_length: u32) -> (usize, usize) {
+    let utf16_end = utf16_offset + utf16_length;
+    let mut utf16_pos = 0u32;
+    let mut byte_start = text.len();
+    let mut byte_end = text.len();
+
+    for (byte_pos, ch) in text.char_indices() {
+        if utf16_pos == utf16_offset {
+            byte_start = byte_pos;
+        }
+        if utf16_pos == utf16_end {
+            byte_end = byte_pos;
+            break;
+        }
+        utf16_pos += ch.len_utf16() as u32;
+    }
 
-impl sapi_lite::tts::EventHandler for SapiEventHandler {
-    fn on_speech_finished(&self, _id: u32) {}
+    if utf16_pos == utf16_offset {
+        byte_start = text.len();
+    }
+
+    (byte_start, byte_end.max(byte_start))
+}
+
+/// Handles backend events, which backends fire from their own event threads.
+/// Word boundaries are forwarded to the `AppWindow`, marshalled onto the UI
+/// thread; a finished utterance advances the `SpeechApp`'s pending queue
+/// directly on the calling thread.
+struct AppEventHandler {
+    app_window: Arc<Mutex<Option<slint::Weak<AppWindow>>>>,
+    speech_app: Arc<Mutex<Option<Weak<Mutex<SpeechApp>>>>>,
+    spoken_text: Arc<Mutex<String>>,
+}
+
+impl EventHandler for AppEventHandler {
+    fn on_speech_finished(&self, _id: u32) {
+        if let Some(speech_app) = self.speech_app.lock().as_ref().and_then(Weak::upgrade) {
+            speech_app.lock().advance_queue();
+        }
+    }
+
+    fn on_word_boundary(&self, utf16_offset: u32, utf16_length: u32) {
+        let spoken_text = self.spoken_text.lock();
+        let (start, end) = utf16_span_to_byte_range(&spoken_text, utf16_offset, utf16_length);
+        let text = spoken_text.clone();
+        drop(spoken_text);
+
+        let app_window = self.app_window.clone();
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(app_window) = app_window.lock().as_ref().and_then(slint::Weak::upgrade) else {
+                return;
+            };
+            app_window.set_spoken_text(slint::SharedString::from(text));
+            app_window.invoke_set_highlight(start as i32, end as i32);
+        });
+    }
 }
 
 struct SpeechApp {
-    synth: sapi_lite::tts::EventfulSynthesizer,
-    voices: Vec<sapi_lite::tts::Voice>,
+    backend: Box<dyn SpeechBackend>,
+    voices: Vec<backend::VoiceInfo>,
     config: Config,
+    spoken_text: Arc<Mutex<String>>,
+    pending_utterances: std::collections::VecDeque<String>,
 }
 
 impl SpeechApp {
-    fn build(config: Config) -> Result<Self, Box<dyn Error>> {
-        sapi_lite::initialize()?;
+    fn build(config: Config, handler: AppEventHandler) -> Result<Self, Box<dyn Error>> {
+        let spoken_text = handler.spoken_text.clone();
+        let backend = backend::build_backend(handler)?;
+        let voices = backend.list_voices();
 
         let mut speech_app = Self {
-            synth: sapi_lite::tts::EventfulSynthesizer::new(SapiEventHandler)?,
-            voices: sapi_lite::tts::installed_voices(None, None)?.collect(),
+            backend,
+            voices,
             config,
+            spoken_text,
+            pending_utterances: std::collections::VecDeque::new(),
         };
 
         speech_app.set_voice(None)?;
@@ -87,16 +155,9 @@ impl SpeechApp {
         Ok(speech_app)
     }
 
-    fn get_voice_by_name(&self, voice_name: Option<&str>) -> Option<&sapi_lite::tts::Voice> {
-        let voice_name = voice_name.unwrap_or(&self.config.voice_name);
-        self.voices
-            .iter()
-            .find(|voice| get_voice_name(voice) == voice_name)
-    }
-
     fn get_voice_name_by_index(&self, index: usize) -> String {
         if index < self.voices.len() {
-            get_voice_name(&self.voices[index])
+            self.voices[index].name.clone()
         } else {
             String::from("")
         }
@@ -104,9 +165,7 @@ impl SpeechApp {
 
     fn get_voice_index(&self, voice_name: Option<&str>) -> Option<usize> {
         let voice_name = voice_name.unwrap_or(&self.config.voice_name);
-        self.voices
-            .iter()
-            .position(|voice| get_voice_name(voice) == voice_name)
+        self.voices.iter().position(|voice| voice.name == voice_name)
     }
 
     fn set_voice(&mut self, voice_name: Option<&str>) -> Result<(), Box<dyn Error>> {
@@ -115,12 +174,7 @@ impl SpeechApp {
             self.config.store();
         }
 
-        if let Some(voice) = self.get_voice_by_name(voice_name) {
-            self.synth.set_voice(voice)?;
-        } else if !self.voices.is_empty() {
-            self.synth.set_voice(&self.voices[0])?;
-        }
-        Ok(())
+        self.backend.set_voice(Some(&self.config.voice_name))
     }
 
     fn set_rate(&mut self, rate: Option<i32>) -> Result<(), Box<dyn Error>> {
@@ -129,7 +183,7 @@ impl SpeechApp {
             self.config.store();
         }
 
-        Ok(self.synth.set_rate(self.config.rate)?)
+        self.backend.set_rate(self.config.rate)
     }
 
     fn set_volume(&mut self, volume: Option<u32>) -> Result<(), Box<dyn Error>> {
@@ -138,24 +192,85 @@ impl SpeechApp {
             self.config.store();
         }
 
-        Ok(self.synth.set_volume(self.config.volume)?)
+        self.backend.set_volume(self.config.volume)
     }
 
+    /// Speaks `speech`, treating it as markup the backend understands if
+    /// `detect_markup` is on and the backend agrees it looks like markup.
+    /// `accepts_markup` only checks that markup is well-formed, not that the
+    /// engine actually accepts every element in it, so if the backend
+    /// rejects it outright this falls back to speaking it literally instead
+    /// of failing the whole call.
     fn speak(&mut self, speech: &str) -> Result<u32, Box<dyn Error>> {
-        // TODO: Find a better way to stop active speech
-        self.synth = sapi_lite::tts::EventfulSynthesizer::new(SapiEventHandler)?;
+        *self.spoken_text.lock() = String::from(speech);
 
-        self.set_voice(None)?;
-        self.set_rate(None)?;
-        self.set_volume(None)?;
+        if self.config.detect_markup && self.backend.accepts_markup(speech) {
+            if let Ok(id) = self.backend.speak(speech) {
+                return Ok(id);
+            }
+        }
 
-        Ok(self.synth.speak(speech)?)
+        let literal = self.backend.format_speech(speech, self.config.pitch);
+        self.backend.speak(&literal)
+    }
+
+    fn set_pitch(&mut self, pitch: Option<i32>) -> Result<(), Box<dyn Error>> {
+        if let Some(pitch) = pitch {
+            self.config.pitch = pitch;
+            self.config.store();
+        }
+
+        Ok(())
+    }
+
+    /// Speaks `speech` immediately, unless queue mode is enabled and speech
+    /// is already in progress, in which case `speech` is appended to the
+    /// pending-utterance queue and spoken once the current one finishes.
+    fn speak_or_enqueue(&mut self, speech: &str) -> Result<(), Box<dyn Error>> {
+        if self.config.queue_mode && self.backend.is_speaking() {
+            self.pending_utterances.push_back(String::from(speech));
+            Ok(())
+        } else {
+            self.speak(speech)?;
+            Ok(())
+        }
+    }
+
+    /// Synthesizes `speech` to an audio file at `path` instead of the
+    /// speakers, applying the same markup handling as [`speak`](Self::speak).
+    fn speak_to_file(&mut self, speech: &str, path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+        if self.config.detect_markup && self.backend.accepts_markup(speech) {
+            if self.backend.synthesize_to_file(speech, path).is_ok() {
+                return Ok(());
+            }
+        }
+
+        let literal = self.backend.format_speech(speech, self.config.pitch);
+        self.backend.synthesize_to_file(&literal, path)
+    }
+
+    /// Speaks the next queued utterance, if any. Called when the current
+    /// utterance finishes.
+    fn advance_queue(&mut self) {
+        if let Some(speech) = self.pending_utterances.pop_front() {
+            let _ = self.speak(&speech);
+        }
+    }
+
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> {
+        self.backend.pause()
+    }
+
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> {
+        self.backend.resume()
     }
-}
 
-impl Drop for SpeechApp {
-    fn drop(&mut self) {
-        sapi_lite::finalize();
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        self.backend.stop()
+    }
+
+    fn is_speaking(&self) -> bool {
+        self.backend.is_speaking()
     }
 }
 
@@ -185,7 +300,7 @@ impl clipboard_master::ClipboardHandler for ClipboardListener {
     fn on_clipboard_change(&mut self) -> clipboard_master::CallbackResult {
         if let Ok(text) = self.clipboard.get_text() {
             if let Some(speech_app) = Weak::upgrade(&self.speech_app) {
-                let _ = speech_app.lock().speak(&text);
+                let _ = speech_app.lock().speak_or_enqueue(&text);
             }
         }
         clipboard_master::CallbackResult::Next
@@ -196,59 +311,31 @@ impl clipboard_master::ClipboardHandler for ClipboardListener {
     }
 }
 
-pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
-    let mut config;
-    {
-        let original_config = Config::load(false);
-        config = Config::load(true);
-
-        if let Some(hidden) = hidden {
-            config.hidden = hidden;
-        }
-
-        if config != original_config {
-            config.store();
-        }
-    }
-
-    let speech_app = Arc::new(Mutex::new(SpeechApp::build(config)?));
-
-    ClipboardListener::spawn(Arc::downgrade(&speech_app));
+/// Builds the `SpeechApp` and `AppWindow`, and wires up every control that
+/// doesn't depend on desktop-only machinery (OS clipboard, system tray,
+/// native file dialogs). Shared by the desktop and wasm entry points so the
+/// wasm build doesn't need to stub out things it never calls.
+fn build_app(config: Config) -> Result<(Arc<Mutex<SpeechApp>>, AppWindow), Box<dyn Error>> {
+    let event_handler_app_window = Arc::new(Mutex::new(None));
+    let event_handler_speech_app = Arc::new(Mutex::new(None));
+    let event_handler = AppEventHandler {
+        app_window: event_handler_app_window.clone(),
+        speech_app: event_handler_speech_app.clone(),
+        spoken_text: Arc::new(Mutex::new(String::new())),
+    };
+    let speech_app = Arc::new(Mutex::new(SpeechApp::build(config, event_handler)?));
+    *event_handler_speech_app.lock() = Some(Arc::downgrade(&speech_app));
 
     let app_window = AppWindow::new()?;
     app_window.set_app_name(slint::SharedString::from(APP_NAME));
-
-    let _tray_icon;
-    {
-        let weak_app_window = app_window.as_weak();
-        let icon = tray_icon::Icon::from_resource_name("app-icon", None)?;
-        _tray_icon = tray_icon::TrayIconBuilder::new()
-            .with_tooltip(APP_NAME)
-            .with_icon(icon)
-            .build()
-            .unwrap();
-        tray_icon::TrayIconEvent::set_event_handler(Some(move |event| {
-            if let tray_icon::TrayIconEvent::DoubleClick { .. } = event {
-                let weak_app_window = weak_app_window.clone();
-                slint::invoke_from_event_loop(move || {
-                    let app_window = weak_app_window.unwrap();
-                    if app_window.window().is_visible() {
-                        app_window.hide().unwrap();
-                    } else {
-                        app_window.show().unwrap();
-                    }
-                })
-                .unwrap();
-            }
-        }));
-    }
+    *event_handler_app_window.lock() = Some(app_window.as_weak());
 
     {
         let v: Vec<slint::StandardListViewItem> = speech_app
             .lock()
             .voices
             .iter()
-            .map(|voice| slint::StandardListViewItem::from(get_voice_name(voice).as_str()))
+            .map(|voice| slint::StandardListViewItem::from(voice.name.as_str()))
             .collect();
         let model = slint::ModelRc::new(slint::VecModel::<slint::StandardListViewItem>::from(v));
         app_window.set_voices_list_model(model);
@@ -259,11 +346,7 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
 
     app_window.set_rate(speech_app.lock().config.rate as f32);
     app_window.set_volume(speech_app.lock().config.volume as f32);
-
-    app_window.window().on_close_requested(|| {
-        slint::quit_event_loop().unwrap();
-        slint::CloseRequestResponse::HideWindow
-    });
+    app_window.set_pitch(speech_app.lock().config.pitch as f32);
 
     app_window.on_voices_list_current_item_changed({
         let speech_app = speech_app.clone();
@@ -286,6 +369,31 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
             speech_app.lock().set_volume(Some(volume)).unwrap();
         }
     });
+    app_window.on_pitch_slider_released({
+        let speech_app = speech_app.clone();
+        move |position: f32| {
+            let pitch = position.round() as i32;
+            speech_app.lock().set_pitch(Some(pitch)).unwrap();
+        }
+    });
+    app_window.on_pause_button_clicked({
+        let speech_app = speech_app.clone();
+        move || {
+            let _ = speech_app.lock().pause();
+        }
+    });
+    app_window.on_resume_button_clicked({
+        let speech_app = speech_app.clone();
+        move || {
+            let _ = speech_app.lock().resume();
+        }
+    });
+    app_window.on_stop_button_clicked({
+        let speech_app = speech_app.clone();
+        move || {
+            let _ = speech_app.lock().stop();
+        }
+    });
     app_window.on_about_button_clicked({
         let speech_app = speech_app.clone();
         move || {
@@ -294,6 +402,8 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
             about_window.set_app_name(slint::SharedString::from(APP_NAME));
             about_window.set_app_version(slint::SharedString::from(version));
             about_window.set_hidden(speech_app.lock().config.hidden);
+            about_window.set_queue_mode(speech_app.lock().config.queue_mode);
+            about_window.set_detect_markup(speech_app.lock().config.detect_markup);
 
             about_window.on_hidden_cb_toggled({
                 let weak_about_window = about_window.as_weak();
@@ -304,6 +414,24 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
                     speech_app.lock().config.store();
                 }
             });
+            about_window.on_queue_mode_cb_toggled({
+                let weak_about_window = about_window.as_weak();
+                let speech_app = speech_app.clone();
+                move || {
+                    let about_window = weak_about_window.unwrap();
+                    speech_app.lock().config.queue_mode = about_window.get_queue_mode();
+                    speech_app.lock().config.store();
+                }
+            });
+            about_window.on_detect_markup_cb_toggled({
+                let weak_about_window = about_window.as_weak();
+                let speech_app = speech_app.clone();
+                move || {
+                    let about_window = weak_about_window.unwrap();
+                    speech_app.lock().config.detect_markup = about_window.get_detect_markup();
+                    speech_app.lock().config.store();
+                }
+            });
 
             about_window.show().unwrap();
         }
@@ -313,10 +441,111 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
         let speech_app = speech_app.clone();
         move || {
             let app_window = weak_app_window.unwrap();
-            speech_app
-                .lock()
-                .speak(&app_window.get_test_string())
+            let _ = speech_app.lock().speak(&app_window.get_test_string());
+        }
+    });
+
+    Ok((speech_app, app_window))
+}
+
+/// Desktop entry point: clipboard watching, the system tray, and exporting
+/// to a file all depend on OS facilities that don't exist on wasm, so they
+/// live here rather than in [`build_app`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
+    let mut config;
+    {
+        let original_config = Config::load(false);
+        config = Config::load(true);
+
+        if let Some(hidden) = hidden {
+            config.hidden = hidden;
+        }
+
+        if config != original_config {
+            config.store();
+        }
+    }
+
+    let (speech_app, app_window) = build_app(config)?;
+
+    ClipboardListener::spawn(Arc::downgrade(&speech_app));
+
+    let pause_item = tray_icon::menu::MenuItem::new("Pause", true, None);
+    let resume_item = tray_icon::menu::MenuItem::new("Resume", true, None);
+    let stop_item = tray_icon::menu::MenuItem::new("Stop", true, None);
+    let export_item = tray_icon::menu::MenuItem::new("Export to File...", true, None);
+
+    let _tray_icon;
+    {
+        let weak_app_window = app_window.as_weak();
+        let icon = tray_icon::Icon::from_resource_name("app-icon", None)?;
+        let menu = tray_icon::menu::Menu::new();
+        menu.append_items(&[&pause_item, &resume_item, &stop_item, &export_item])?;
+        _tray_icon = tray_icon::TrayIconBuilder::new()
+            .with_tooltip(APP_NAME)
+            .with_icon(icon)
+            .with_menu(Box::new(menu))
+            .build()
+            .unwrap();
+        tray_icon::TrayIconEvent::set_event_handler(Some(move |event| {
+            if let tray_icon::TrayIconEvent::DoubleClick { .. } = event {
+                let weak_app_window = weak_app_window.clone();
+                slint::invoke_from_event_loop(move || {
+                    let app_window = weak_app_window.unwrap();
+                    if app_window.window().is_visible() {
+                        app_window.hide().unwrap();
+                    } else {
+                        app_window.show().unwrap();
+                    }
+                })
                 .unwrap();
+            }
+        }));
+    }
+
+    {
+        let speech_app = speech_app.clone();
+        let pause_id = pause_item.id().clone();
+        let resume_id = resume_item.id().clone();
+        let stop_id = stop_item.id().clone();
+        let export_id = export_item.id().clone();
+        tray_icon::menu::MenuEvent::set_event_handler(Some(move |event: tray_icon::menu::MenuEvent| {
+            let speech_app = speech_app.clone();
+            if event.id == pause_id {
+                let _ = speech_app.lock().pause();
+            } else if event.id == resume_id {
+                let _ = speech_app.lock().resume();
+            } else if event.id == stop_id {
+                let _ = speech_app.lock().stop();
+            } else if event.id == export_id {
+                let text = speech_app.lock().spoken_text.lock().clone();
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("WAV audio", &["wav"])
+                    .set_file_name("speech.wav")
+                    .save_file()
+                {
+                    let _ = speech_app.lock().speak_to_file(&text, &path);
+                }
+            }
+        }));
+    }
+
+    app_window.window().on_close_requested(|| {
+        slint::quit_event_loop().unwrap();
+        slint::CloseRequestResponse::HideWindow
+    });
+    app_window.on_export_button_clicked({
+        let speech_app = speech_app.clone();
+        move || {
+            let text = speech_app.lock().spoken_text.lock().clone();
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("WAV audio", &["wav"])
+                .set_file_name("speech.wav")
+                .save_file()
+            {
+                let _ = speech_app.lock().speak_to_file(&text, &path);
+            }
         }
     });
 
@@ -328,3 +557,16 @@ pub fn run(hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// wasm entry point: there is no OS clipboard to watch, no system tray, and
+/// no native file dialog, so this skips straight to showing the window.
+#[cfg(target_arch = "wasm32")]
+pub fn run(_hidden: Option<bool>) -> Result<(), Box<dyn Error>> {
+    let config = Config::load(true);
+    let (_speech_app, app_window) = build_app(config)?;
+
+    app_window.show()?;
+    slint::run_event_loop_until_quit()?;
+
+    Ok(())
+}
@@ -1,10 +1,15 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
-    let instance_name = "SimpleTTSReader-{85CBCC28-E397-4fcd-802E-100BE5F064A2}";
-    let instance = single_instance::SingleInstance::new(instance_name).unwrap();
-    if !instance.is_single() {
-        return;
+    // There's no OS-level notion of "another instance" in a browser tab, so
+    // only desktop builds need to guard against a second process.
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let instance_name = "SimpleTTSReader-{85CBCC28-E397-4fcd-802E-100BE5F064A2}";
+        let instance = single_instance::SingleInstance::new(instance_name).unwrap();
+        if !instance.is_single() {
+            return;
+        }
     }
 
     let mut pargs = pico_args::Arguments::from_env();